@@ -3,6 +3,7 @@ pub mod mcp;
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use std::collections::HashMap;
 use std::io::{BufRead, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tauri::{Emitter, Manager};
 use tokio::sync::Mutex;
@@ -65,6 +66,67 @@ struct AppState {
     output_buffers: Arc<std::sync::Mutex<HashMap<String, Vec<u8>>>>,
 }
 
+/// Decode output of wsl.exe.
+/// WSL usually writes UTF-16LE (optionally with a BOM), but newer builds may emit UTF-8.
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return decode_utf16le(&bytes[2..]);
+    }
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(&bytes[3..]).to_string();
+    }
+
+    // ASCII text encoded as UTF-16LE has a NUL high byte in (nearly) every pair,
+    // while UTF-8 output never contains NULs
+    let has_utf16_nuls = bytes.iter().skip(1).step_by(2).any(|&b| b == 0);
+    if !has_utf16_nuls {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return text.to_string();
+        }
+    }
+
+    decode_utf16le(bytes)
+}
+
+fn decode_utf16le(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+
+    let mut text = String::from_utf16_lossy(&units);
+    // A dangling odd byte can't be decoded, but shouldn't vanish silently either
+    if bytes.len() % 2 == 1 {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    text
+}
+
+/// Error text of a failed wsl.exe call, which may be on stderr, stdout, or both
+fn wsl_error_message(output: &std::process::Output) -> String {
+    let stderr = decode_wsl_output(&output.stderr).replace('\u{0}', "");
+    let stdout = decode_wsl_output(&output.stdout).replace('\u{0}', "");
+
+    let parts: Vec<&str> = [stderr.trim(), stdout.trim()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if parts.is_empty() {
+        format!("wsl.exe exited with {}", output.status)
+    } else {
+        parts.join("\n")
+    }
+}
+
+#[derive(serde::Serialize)]
+struct WslDistro {
+    name: String,
+    state: String,   // "Running", "Stopped", "Installing", ...
+    version: u32,
+    default: bool,
+}
+
 #[tauri::command]
 async fn get_wsl_distros() -> Result<Vec<String>, String> {
     let output = silent_command("wsl.exe")
@@ -73,35 +135,7 @@ async fn get_wsl_distros() -> Result<Vec<String>, String> {
         .map_err(|e| e.to_string())?;
 
     // WSL outputs UTF-16LE, need to decode properly
-    let stdout = if output.stdout.len() >= 2 && output.stdout[0] == 0xFF && output.stdout[1] == 0xFE {
-        // Has BOM, skip it
-        String::from_utf16_lossy(
-            &output.stdout[2..]
-                .chunks(2)
-                .filter_map(|c| {
-                    if c.len() == 2 {
-                        Some(u16::from_le_bytes([c[0], c[1]]))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<u16>>()
-        )
-    } else {
-        // Try UTF-16LE without BOM
-        String::from_utf16_lossy(
-            &output.stdout
-                .chunks(2)
-                .filter_map(|c| {
-                    if c.len() == 2 {
-                        Some(u16::from_le_bytes([c[0], c[1]]))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<u16>>()
-        )
-    };
+    let stdout = decode_wsl_output(&output.stdout);
 
     let distros: Vec<String> = stdout
         .lines()
@@ -112,6 +146,129 @@ async fn get_wsl_distros() -> Result<Vec<String>, String> {
     Ok(distros)
 }
 
+/// Get installed distros with their state, WSL version and default flag
+#[tauri::command]
+async fn get_wsl_distro_status() -> Result<Vec<WslDistro>, String> {
+    let output = silent_command("wsl.exe")
+        .args(["--list", "--verbose"])
+        .output()
+        .map_err(|e| format!("WSL not available: {}", e))?;
+
+    // With no distros installed wsl.exe prints a hint and exits non-zero;
+    // parse whatever it printed so that case yields an empty list like get_wsl_distros
+    let stdout = decode_wsl_output(&output.stdout);
+    if !output.status.success() && stdout.replace('\u{0}', "").trim().is_empty() {
+        return Err(format!("Failed to list distros: {}", wsl_error_message(&output)));
+    }
+
+    Ok(parse_wsl_verbose_list(&stdout))
+}
+
+/// Parse `wsl --list --verbose` output:
+/// ```text
+///   NAME      STATE      VERSION
+/// * Ubuntu    Running    2
+///   Debian    Stopped    2
+/// ```
+fn parse_wsl_verbose_list(text: &str) -> Vec<WslDistro> {
+    let mut distros = Vec::new();
+
+    // First line is the (localized) header
+    for line in text.lines().skip(1) {
+        let line = line.replace("\u{0}", "");
+        let line = line.trim();
+        let default = line.starts_with('*');
+        let parts: Vec<&str> = line.trim_start_matches('*').split_whitespace().collect();
+
+        if parts.len() < 3 || parts[0].contains("docker-desktop") {
+            continue;
+        }
+
+        // Rows without a numeric VERSION are messages, not distros
+        // (e.g. the hint printed when no distributions are installed)
+        let Ok(version) = parts[2].parse() else {
+            continue;
+        };
+
+        distros.push(WslDistro {
+            name: parts[0].to_string(),
+            state: parts[1].to_string(),
+            version,
+            default,
+        });
+    }
+
+    distros
+}
+
+/// Shut down a running distro
+#[tauri::command]
+async fn wsl_terminate(distro: String) -> Result<(), String> {
+    let output = silent_command("wsl.exe")
+        .args(["--terminate", &distro])
+        .output()
+        .map_err(|e| format!("WSL not available: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Terminate failed: {}", wsl_error_message(&output)));
+    }
+
+    Ok(())
+}
+
+/// Make a distro the default for `wsl.exe` without `-d`
+#[tauri::command]
+async fn wsl_set_default(distro: String) -> Result<(), String> {
+    let output = silent_command("wsl.exe")
+        .args(["--set-default", &distro])
+        .output()
+        .map_err(|e| format!("WSL not available: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Set default failed: {}", wsl_error_message(&output)));
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct CommandOutput {
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+}
+
+/// Run a one-shot command in WSL and capture its output, without opening a PTY.
+/// This is the non-interactive companion to `spawn_shell`.
+#[tauri::command]
+async fn run_wsl_command(
+    distro: Option<String>,
+    command: String,
+    cwd: Option<String>,
+) -> Result<CommandOutput, String> {
+    let mut cmd = silent_command("wsl.exe");
+    if let Some(d) = &distro {
+        cmd.args(["-d", d]);
+    }
+    // --cd accepts both Linux paths and Windows paths
+    if let Some(dir) = &cwd {
+        cmd.args(["--cd", dir]);
+    }
+    // -e runs bash directly (no outer shell), so the command reaches `bash -lc` as one argument
+    cmd.args(["-e", "bash", "-lc", &command]);
+
+    let output = cmd.output()
+        .map_err(|e| format!("WSL not available: {}", e))?;
+
+    // stdout is the Linux command's own output; only stderr may carry wsl.exe's UTF-16 errors
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: decode_wsl_output(&output.stderr),
+        // No exit code means the process was killed by a signal
+        exit_code: output.status.code().unwrap_or(-1),
+    })
+}
+
 #[tauri::command]
 async fn spawn_shell(
     tab_id: String,
@@ -1603,7 +1760,7 @@ async fn docker_start(container_id: String) -> Result<(), String> {
     let output = silent_command("docker")
         .args(["start", &container_id])
         .output()
-        .map_err(|e| format!("Failed to start container: {}", e))?;
+        .map_err(|e| format!("Docker not available: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1619,7 +1776,7 @@ async fn docker_stop(container_id: String) -> Result<(), String> {
     let output = silent_command("docker")
         .args(["stop", &container_id])
         .output()
-        .map_err(|e| format!("Failed to stop container: {}", e))?;
+        .map_err(|e| format!("Docker not available: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1635,7 +1792,7 @@ async fn docker_restart(container_id: String) -> Result<(), String> {
     let output = silent_command("docker")
         .args(["restart", &container_id])
         .output()
-        .map_err(|e| format!("Failed to restart container: {}", e))?;
+        .map_err(|e| format!("Docker not available: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1651,7 +1808,7 @@ async fn docker_remove(container_id: String) -> Result<(), String> {
     let output = silent_command("docker")
         .args(["rm", "-f", &container_id])
         .output()
-        .map_err(|e| format!("Failed to remove container: {}", e))?;
+        .map_err(|e| format!("Docker not available: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1661,24 +1818,192 @@ async fn docker_remove(container_id: String) -> Result<(), String> {
     Ok(())
 }
 
-/// IPC response channel for MCP communication
-type IpcResponseTx = Arc<Mutex<Option<tokio::sync::oneshot::Sender<serde_json::Value>>>>;
+/// Get the last lines of a container's logs (non-following)
+#[tauri::command]
+async fn docker_logs(container_id: String, tail: Option<u32>) -> Result<String, String> {
+    let tail_str = tail.unwrap_or(100).to_string();
+
+    let output = silent_command("docker")
+        .args(["logs", "--tail", &tail_str, &container_id])
+        .output()
+        .map_err(|e| format!("Docker not available: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to get logs: {}", stderr));
+    }
+
+    // docker logs replays the container's stdout and stderr on the matching streams
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+/// Default time to wait for the frontend to answer an IPC request
+const IPC_DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// Lower bound for client-supplied IPC timeouts
+const IPC_MIN_TIMEOUT_MS: u64 = 1000;
+
+/// Upper bound for client-supplied IPC timeouts (10 minutes)
+const IPC_MAX_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+
+/// Extra time on top of a command's output wait, so a response sent right at
+/// the deadline still reaches the client
+const IPC_OUTPUT_WAIT_MARGIN_MS: u64 = 5000;
+
+/// Pending IPC response channels for MCP communication, keyed by request id
+type IpcResponseMap = Arc<Mutex<HashMap<u64, tokio::sync::oneshot::Sender<serde_json::Value>>>>;
 
 /// State for IPC communication
+#[derive(Clone)]
 struct IpcState {
-    response_tx: IpcResponseTx,
+    pending: IpcResponseMap,
+    next_id: Arc<AtomicU64>,
+    // Token every IPC client must present, shared via a user-only file
+    token: String,
+}
+
+impl IpcState {
+    fn new(token: String) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            token,
+        }
+    }
+
+    /// Allocate a new request id and register its response channel
+    async fn register(&self) -> (u64, tokio::sync::oneshot::Receiver<serde_json::Value>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+        (id, rx)
+    }
+
+    /// Deliver a response to the request waiting on `id`.
+    /// Returns false if no such request is pending (already answered or timed out).
+    async fn resolve(&self, id: u64, response: serde_json::Value) -> bool {
+        match self.pending.lock().await.remove(&id) {
+            Some(tx) => tx.send(response).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Drop the response channel of a request that will no longer be awaited
+    async fn cancel(&self, id: u64) {
+        self.pending.lock().await.remove(&id);
+    }
 }
 
 /// Handle IPC response from frontend
 #[tauri::command]
-async fn ipc_response(response: serde_json::Value, state: tauri::State<'_, IpcState>) -> Result<(), String> {
-    let mut tx_lock = state.response_tx.lock().await;
-    if let Some(tx) = tx_lock.take() {
-        let _ = tx.send(response);
+async fn ipc_response(
+    request_id: u64,
+    response: serde_json::Value,
+    state: tauri::State<'_, IpcState>,
+) -> Result<(), String> {
+    if !state.resolve(request_id, response).await {
+        log::warn!("Dropping IPC response for unknown request id {}", request_id);
     }
     Ok(())
 }
 
+/// Forward a single IPC request to the frontend and wait for its response
+fn handle_ipc_request(
+    app_handle: &tauri::AppHandle,
+    ipc_state: &IpcState,
+    request: serde_json::Value,
+) -> serde_json::Value {
+    let token = request.get("token")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if !mcp::tokens_match(&ipc_state.token, token) {
+        log::warn!("Rejected IPC request with invalid token");
+        return json!({"error": "Unauthorized: invalid or missing IPC token", "code": "unauthorized"});
+    }
+
+    let action = request.get("action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let payload = request.get("payload").cloned().unwrap_or(json!({}));
+
+    // Requests may carry their own timeout (e.g. run_command waiting on a slow build)
+    let wait_for_output = payload.get("wait_for_output")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let timeout_ms = match payload.get("timeout_ms").and_then(|v| v.as_u64()) {
+        // The frontend may spend the whole timeout waiting for output before answering
+        Some(ms) if wait_for_output => ms.saturating_add(IPC_OUTPUT_WAIT_MARGIN_MS),
+        Some(ms) => ms,
+        None => IPC_DEFAULT_TIMEOUT_MS,
+    }
+    .clamp(IPC_MIN_TIMEOUT_MS, IPC_MAX_TIMEOUT_MS);
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        // Create response channel for this request
+        let (request_id, rx) = ipc_state.register().await;
+
+        // Emit event to frontend
+        let _ = app_handle.emit("mcp-action", json!({
+            "request_id": request_id,
+            "action": action,
+            "payload": payload
+        }));
+
+        // Wait for response with timeout
+        let response = tokio::time::timeout(std::time::Duration::from_millis(timeout_ms), rx).await;
+
+        match response {
+            Ok(Ok(v)) => v,
+            Ok(Err(_)) => {
+                ipc_state.cancel(request_id).await;
+                json!({"error": "No response from frontend", "code": "no_response"})
+            }
+            Err(_) => {
+                ipc_state.cancel(request_id).await;
+                json!({
+                    "error": format!("Timed out after {} ms waiting for frontend", timeout_ms),
+                    "code": "timeout"
+                })
+            }
+        }
+    })
+}
+
+/// Serve IPC requests (one JSON object per line) from a connected client
+fn serve_ipc_connection<S: Read + Write>(
+    mut stream: S,
+    reader_stream: S,
+    app_handle: tauri::AppHandle,
+    ipc_state: Arc<IpcState>,
+) {
+    let mut reader = std::io::BufReader::new(reader_stream);
+    let mut line = String::new();
+
+    while reader.read_line(&mut line).is_ok() && !line.is_empty() {
+        if let Ok(request) = serde_json::from_str::<serde_json::Value>(&line) {
+            let response_value = handle_ipc_request(&app_handle, &ipc_state, request);
+
+            // Send response back
+            let response_str = serde_json::to_string(&response_value).unwrap();
+            let _ = stream.write_all(response_str.as_bytes());
+            let _ = stream.write_all(b"\n");
+            let _ = stream.flush();
+        }
+        line.clear();
+    }
+}
+
+/// Write the auth token for MCP clients; call only once the IPC listener is bound
+fn publish_ipc_token(token: &str) {
+    match mcp::write_ipc_token(token) {
+        Ok(path) => log::info!("IPC token written to {}", path.display()),
+        Err(e) => log::error!("Failed to write IPC token: {}", e),
+    }
+}
+
 /// Start IPC server for MCP communication
 fn start_ipc_server(app_handle: tauri::AppHandle, ipc_state: Arc<IpcState>) {
     std::thread::spawn(move || {
@@ -1696,63 +2021,18 @@ fn start_ipc_server(app_handle: tauri::AppHandle, ipc_state: Arc<IpcState>) {
 
             log::info!("IPC server listening on 127.0.0.1:45892");
 
+            // Only the instance that owns the listener may publish its token
+            publish_ipc_token(&ipc_state.token);
+
             for stream in listener.incoming() {
                 match stream {
-                    Ok(mut stream) => {
+                    Ok(stream) => {
                         let app_handle = app_handle.clone();
                         let ipc_state = ipc_state.clone();
 
                         std::thread::spawn(move || {
-                            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
-                            let mut line = String::new();
-
-                            while reader.read_line(&mut line).is_ok() && !line.is_empty() {
-                                if let Ok(request) = serde_json::from_str::<serde_json::Value>(&line) {
-                                    let action = request.get("action")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("");
-                                    let payload = request.get("payload").cloned().unwrap_or(json!({}));
-
-                                    // Create response channel
-                                    let (tx, rx) = tokio::sync::oneshot::channel();
-
-                                    // Store sender in state
-                                    {
-                                        let rt = tokio::runtime::Runtime::new().unwrap();
-                                        rt.block_on(async {
-                                            let mut tx_lock = ipc_state.response_tx.lock().await;
-                                            *tx_lock = Some(tx);
-                                        });
-                                    }
-
-                                    // Emit event to frontend
-                                    let _ = app_handle.emit("mcp-action", json!({
-                                        "action": action,
-                                        "payload": payload
-                                    }));
-
-                                    // Wait for response with timeout
-                                    let rt = tokio::runtime::Runtime::new().unwrap();
-                                    let response = rt.block_on(async {
-                                        tokio::time::timeout(
-                                            std::time::Duration::from_secs(30),
-                                            rx
-                                        ).await
-                                    });
-
-                                    let response_value = match response {
-                                        Ok(Ok(v)) => v,
-                                        _ => json!({"error": "Timeout or no response"}),
-                                    };
-
-                                    // Send response back
-                                    let response_str = serde_json::to_string(&response_value).unwrap();
-                                    let _ = stream.write_all(response_str.as_bytes());
-                                    let _ = stream.write_all(b"\n");
-                                    let _ = stream.flush();
-                                }
-                                line.clear();
-                            }
+                            let reader_stream = stream.try_clone().unwrap();
+                            serve_ipc_connection(stream, reader_stream, app_handle, ipc_state);
                         });
                     }
                     Err(e) => log::error!("IPC connection error: {}", e),
@@ -1782,63 +2062,18 @@ fn start_ipc_server(app_handle: tauri::AppHandle, ipc_state: Arc<IpcState>) {
 
             log::info!("IPC server listening on {}", socket_path);
 
+            // Only the instance that owns the listener may publish its token
+            publish_ipc_token(&ipc_state.token);
+
             for stream in listener.incoming() {
                 match stream {
-                    Ok(mut stream) => {
+                    Ok(stream) => {
                         let app_handle = app_handle.clone();
                         let ipc_state = ipc_state.clone();
 
                         std::thread::spawn(move || {
-                            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
-                            let mut line = String::new();
-
-                            while reader.read_line(&mut line).is_ok() && !line.is_empty() {
-                                if let Ok(request) = serde_json::from_str::<serde_json::Value>(&line) {
-                                    let action = request.get("action")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("");
-                                    let payload = request.get("payload").cloned().unwrap_or(json!({}));
-
-                                    // Create response channel
-                                    let (tx, rx) = tokio::sync::oneshot::channel();
-
-                                    // Store sender in state
-                                    {
-                                        let rt = tokio::runtime::Runtime::new().unwrap();
-                                        rt.block_on(async {
-                                            let mut tx_lock = ipc_state.response_tx.lock().await;
-                                            *tx_lock = Some(tx);
-                                        });
-                                    }
-
-                                    // Emit event to frontend
-                                    let _ = app_handle.emit("mcp-action", json!({
-                                        "action": action,
-                                        "payload": payload
-                                    }));
-
-                                    // Wait for response with timeout
-                                    let rt = tokio::runtime::Runtime::new().unwrap();
-                                    let response = rt.block_on(async {
-                                        tokio::time::timeout(
-                                            std::time::Duration::from_secs(30),
-                                            rx
-                                        ).await
-                                    });
-
-                                    let response_value = match response {
-                                        Ok(Ok(v)) => v,
-                                        _ => json!({"error": "Timeout or no response"}),
-                                    };
-
-                                    // Send response back
-                                    let response_str = serde_json::to_string(&response_value).unwrap();
-                                    let _ = stream.write_all(response_str.as_bytes());
-                                    let _ = stream.write_all(b"\n");
-                                    let _ = stream.flush();
-                                }
-                                line.clear();
-                            }
+                            let reader_stream = stream.try_clone().unwrap();
+                            serve_ipc_connection(stream, reader_stream, app_handle, ipc_state);
                         });
                     }
                     Err(e) => log::error!("IPC connection error: {}", e),
@@ -1850,9 +2085,7 @@ fn start_ipc_server(app_handle: tauri::AppHandle, ipc_state: Arc<IpcState>) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let ipc_state = Arc::new(IpcState {
-        response_tx: Arc::new(Mutex::new(None)),
-    });
+    let ipc_state = Arc::new(IpcState::new(mcp::generate_ipc_token()));
 
     let ipc_state_clone = ipc_state.clone();
 
@@ -1865,9 +2098,7 @@ pub fn run() {
             processes: Arc::new(Mutex::new(HashMap::new())),
             output_buffers: Arc::new(std::sync::Mutex::new(HashMap::new())),
         })
-        .manage(IpcState {
-            response_tx: ipc_state.response_tx.clone(),
-        })
+        .manage(ipc_state.as_ref().clone())
         .invoke_handler(tauri::generate_handler![
             spawn_shell,
             write_to_shell,
@@ -1875,6 +2106,10 @@ pub fn run() {
             kill_shell,
             get_shell_buffer,
             get_wsl_distros,
+            get_wsl_distro_status,
+            wsl_terminate,
+            wsl_set_default,
+            run_wsl_command,
             get_git_info,
             get_docker_status,
             list_projects,
@@ -1914,7 +2149,8 @@ pub fn run() {
             docker_start,
             docker_stop,
             docker_restart,
-            docker_remove
+            docker_remove,
+            docker_logs
         ])
         .setup(move |app| {
             if cfg!(debug_assertions) {
@@ -1939,3 +2175,94 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn overlapping_ipc_requests_receive_their_own_response() {
+        let state = IpcState::new(mcp::generate_ipc_token());
+
+        // Two requests arrive before the frontend answers either of them
+        let (first_id, first_rx) = state.register().await;
+        let (second_id, second_rx) = state.register().await;
+        assert_ne!(first_id, second_id);
+
+        // The frontend answers them out of order
+        assert!(state.resolve(second_id, json!({"tab_id": "second"})).await);
+        assert!(state.resolve(first_id, json!({"tab_id": "first"})).await);
+
+        assert_eq!(first_rx.await.unwrap(), json!({"tab_id": "first"}));
+        assert_eq!(second_rx.await.unwrap(), json!({"tab_id": "second"}));
+
+        // A late duplicate response is not delivered anywhere
+        assert!(!state.resolve(first_id, json!({})).await);
+        assert!(state.pending.lock().await.is_empty());
+    }
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn decode_wsl_output_with_bom() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16le("Ubuntu\r\nDebian\r\n"));
+        assert_eq!(decode_wsl_output(&bytes), "Ubuntu\r\nDebian\r\n");
+    }
+
+    #[test]
+    fn decode_wsl_output_without_bom() {
+        let bytes = utf16le("Ubuntu-22.04\r\n");
+        assert_eq!(decode_wsl_output(&bytes), "Ubuntu-22.04\r\n");
+    }
+
+    #[test]
+    fn decode_wsl_output_plain_utf8() {
+        let bytes = "  NAME      STATE      VERSION\n* Ubuntu    Running    2\n".as_bytes();
+        assert_eq!(
+            decode_wsl_output(bytes),
+            "  NAME      STATE      VERSION\n* Ubuntu    Running    2\n"
+        );
+    }
+
+    #[test]
+    fn decode_wsl_output_keeps_trailing_odd_byte() {
+        let mut bytes = utf16le("Ubuntu\n");
+        bytes.push(b'x');
+        assert_eq!(decode_wsl_output(&bytes), "Ubuntu\n\u{FFFD}");
+    }
+
+    #[test]
+    fn parse_wsl_verbose_list_reads_table_rows() {
+        let text = concat!(
+            "  NAME                   STATE           VERSION\r\n",
+            "* Ubuntu                 Running         2\r\n",
+            "  docker-desktop         Stopped         2\r\n",
+            "  Debian                 Stopped         1\r\n",
+        );
+
+        let distros = parse_wsl_verbose_list(text);
+
+        assert_eq!(distros.len(), 2);
+        assert_eq!(distros[0].name, "Ubuntu");
+        assert_eq!(distros[0].state, "Running");
+        assert_eq!(distros[0].version, 2);
+        assert!(distros[0].default);
+        assert_eq!(distros[1].name, "Debian");
+        assert_eq!(distros[1].version, 1);
+        assert!(!distros[1].default);
+    }
+
+    #[test]
+    fn parse_wsl_verbose_list_ignores_messages() {
+        let text = concat!(
+            "Windows Subsystem for Linux has no installed distributions.\r\n",
+            "Use 'wsl.exe --list --online' to list available distributions\r\n",
+            "and 'wsl.exe --install <Distro>' to install.\r\n",
+        );
+
+        assert!(parse_wsl_verbose_list(text).is_empty());
+    }
+}
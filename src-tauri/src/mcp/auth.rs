@@ -0,0 +1,73 @@
+use rand::{distributions::Alphanumeric, Rng};
+use std::path::PathBuf;
+
+const TOKEN_LENGTH: usize = 48;
+
+/// Path of the IPC auth token file, inside the app data dir
+/// (same directory Tauri resolves for the `dev.wsl-terminal.app` identifier)
+pub fn ipc_token_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("dev.wsl-terminal.app").join("ipc-token"))
+}
+
+/// Generate a random token for authenticating IPC clients
+pub fn generate_ipc_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Write the IPC token so that only the current user can read it
+pub fn write_ipc_token(token: &str) -> Result<PathBuf, String> {
+    use std::io::Write;
+
+    let path = ipc_token_path().ok_or("Could not find app data directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create token directory: {}", e))?;
+    }
+
+    // Remove any stale token first so the permissions below apply to a fresh file
+    let _ = std::fs::remove_file(&path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    // On Windows the app data dir is already restricted to the current user by its ACL
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create token file: {}", e))?;
+    file.write_all(token.as_bytes())
+        .map_err(|e| format!("Failed to write token file: {}", e))?;
+
+    Ok(path)
+}
+
+/// Read the IPC token written by the running app
+pub fn read_ipc_token() -> Result<String, String> {
+    let path = ipc_token_path().ok_or("Could not find app data directory")?;
+    std::fs::read_to_string(&path)
+        .map(|t| t.trim().to_string())
+        .map_err(|e| format!("Failed to read IPC token from {}: {}. Is WSL Terminal running?", path.display(), e))
+}
+
+/// Compare tokens without short-circuiting on the first mismatching byte
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    let expected = expected.as_bytes();
+    let provided = provided.as_bytes();
+    if expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(provided)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
@@ -1,6 +1,8 @@
+mod auth;
 mod server;
 mod tools;
 mod types;
 
+pub use auth::{generate_ipc_token, read_ipc_token, tokens_match, write_ipc_token};
 pub use server::run_mcp_server;
 pub use types::*;
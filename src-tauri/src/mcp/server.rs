@@ -1,15 +1,58 @@
+use super::auth::read_ipc_token;
 use super::tools::get_tools;
 use super::types::*;
 use serde_json::json;
 use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 const VERSION: &str = "0.4.0";
 
+/// How often to check whether the Docker daemon came up or went away
+const DOCKER_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Whether the Docker daemon was reachable at the last check (docker tools are listed only then)
+static DOCKER_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Set once the client sent `initialized`; no notifications are pushed before that
+static CLIENT_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Stdout shared by responses and server-initiated notifications.
+/// Every message is written as one complete line under the lock, so they never interleave.
+struct Outbound {
+    stdout: Mutex<io::Stdout>,
+}
+
+impl Outbound {
+    fn new() -> Self {
+        Self {
+            stdout: Mutex::new(io::stdout()),
+        }
+    }
+
+    fn send(&self, message: &serde_json::Value) {
+        let line = serde_json::to_string(message).unwrap();
+        let mut stdout = self.stdout.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(stdout, "{}", line) {
+            eprintln!("[MCP] Write error: {}", e);
+        }
+        let _ = stdout.flush();
+    }
+
+    fn notify(&self, method: &str) {
+        let notification = JsonRpcNotification::new(method);
+        self.send(&serde_json::to_value(notification).unwrap());
+    }
+}
+
 pub fn run_mcp_server() {
     eprintln!("[MCP] WSL Terminal MCP Server v{} starting...", VERSION);
 
     let stdin = io::stdin();
-    let mut stdout = io::stdout();
+    let outbound = Arc::new(Outbound::new());
+
+    spawn_tool_watcher(outbound.clone());
 
     for line in stdin.lock().lines() {
         let line = match line {
@@ -24,28 +67,97 @@ pub fn run_mcp_server() {
             continue;
         }
 
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[MCP] Parse error: {}", e);
-                let response = JsonRpcResponse::error(
-                    None,
-                    -32700,
-                    &format!("Parse error: {}", e),
-                );
-                let _ = writeln!(stdout, "{}", serde_json::to_string(&response).unwrap());
-                let _ = stdout.flush();
-                continue;
+        if let Some(reply) = handle_message(&line) {
+            outbound.send(&reply);
+        }
+    }
+}
+
+/// Poll for changes in the available tool set and notify the client through `outbound`.
+/// The first probe runs immediately: if it finishes before the client is initialized the
+/// first tools/list already has docker tools, otherwise list_changed announces them.
+fn spawn_tool_watcher(outbound: Arc<Outbound>) {
+    std::thread::spawn(move || loop {
+        let available = docker_available();
+        let previous = DOCKER_AVAILABLE.swap(available, Ordering::SeqCst);
+
+        if available != previous {
+            eprintln!("[MCP] Docker is now {}", if available { "available" } else { "unavailable" });
+            if CLIENT_INITIALIZED.load(Ordering::SeqCst) {
+                outbound.notify("notifications/tools/list_changed");
             }
-        };
+        }
 
-        let response = handle_request(&request);
+        std::thread::sleep(DOCKER_POLL_INTERVAL);
+    });
+}
 
-        if let Err(e) = writeln!(stdout, "{}", serde_json::to_string(&response).unwrap()) {
-            eprintln!("[MCP] Write error: {}", e);
+fn docker_available() -> bool {
+    crate::silent_command("docker")
+        .args(["info", "--format", "{{.ServerVersion}}"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Handle one line of input, holding either a single request or a JSON-RPC batch.
+/// Returns None when nothing should be written back (a batch of only notifications).
+fn handle_message(line: &str) -> Option<serde_json::Value> {
+    let message: serde_json::Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return Some(parse_error(&e)),
+    };
+
+    match message {
+        serde_json::Value::Array(items) => handle_batch(items),
+        single => {
+            let request: JsonRpcRequest = match serde_json::from_value(single) {
+                Ok(r) => r,
+                Err(e) => return Some(parse_error(&e)),
+            };
+            Some(serde_json::to_value(handle_request(&request)).unwrap())
+        }
+    }
+}
+
+fn handle_batch(items: Vec<serde_json::Value>) -> Option<serde_json::Value> {
+    if items.is_empty() {
+        let response = JsonRpcResponse::error(None, -32600, "Invalid Request: empty batch");
+        return Some(serde_json::to_value(response).unwrap());
+    }
+
+    let mut responses = Vec::new();
+    for item in items {
+        match serde_json::from_value::<JsonRpcRequest>(item) {
+            Ok(request) => {
+                let response = handle_request(&request);
+                // Notifications (no id) get no response in a batch
+                if request.id.is_some() {
+                    responses.push(response);
+                }
+            }
+            Err(e) => {
+                eprintln!("[MCP] Invalid request in batch: {}", e);
+                responses.push(JsonRpcResponse::error(
+                    None,
+                    -32600,
+                    &format!("Invalid Request: {}", e),
+                ));
+            }
         }
-        let _ = stdout.flush();
     }
+
+    if responses.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_value(responses).unwrap())
+    }
+}
+
+fn parse_error(e: &serde_json::Error) -> serde_json::Value {
+    eprintln!("[MCP] Parse error: {}", e);
+    let response = JsonRpcResponse::error(None, -32700, &format!("Parse error: {}", e));
+    serde_json::to_value(response).unwrap()
 }
 
 fn handle_request(request: &JsonRpcRequest) -> JsonRpcResponse {
@@ -53,7 +165,7 @@ fn handle_request(request: &JsonRpcRequest) -> JsonRpcResponse {
 
     match request.method.as_str() {
         "initialize" => handle_initialize(request),
-        "initialized" => handle_initialized(request),
+        "initialized" | "notifications/initialized" => handle_initialized(request),
         "tools/list" => handle_list_tools(request),
         "tools/call" => handle_call_tool(request),
         "ping" => JsonRpcResponse::success(request.id.clone(), json!({})),
@@ -70,7 +182,7 @@ fn handle_initialize(request: &JsonRpcRequest) -> JsonRpcResponse {
         protocol_version: "2024-11-05".to_string(),
         capabilities: Capabilities {
             tools: Some(ToolsCapability {
-                list_changed: false,
+                list_changed: true,
             }),
         },
         server_info: ServerInfo {
@@ -84,11 +196,14 @@ fn handle_initialize(request: &JsonRpcRequest) -> JsonRpcResponse {
 
 fn handle_initialized(_request: &JsonRpcRequest) -> JsonRpcResponse {
     eprintln!("[MCP] Client initialized");
+    CLIENT_INITIALIZED.store(true, Ordering::SeqCst);
     JsonRpcResponse::success(None, json!({}))
 }
 
 fn handle_list_tools(request: &JsonRpcRequest) -> JsonRpcResponse {
-    let result = ListToolsResult { tools: get_tools() };
+    let result = ListToolsResult {
+        tools: get_tools(DOCKER_AVAILABLE.load(Ordering::SeqCst)),
+    };
     JsonRpcResponse::success(request.id.clone(), serde_json::to_value(result).unwrap())
 }
 
@@ -115,6 +230,7 @@ fn execute_tool(name: &str, args: serde_json::Value) -> ToolResult {
         "open_tab" => tool_open_tab(args),
         "close_tab" => tool_close_tab(args),
         "focus_tab" => tool_focus_tab(args),
+        "rename_tab" => tool_rename_tab(args),
         "get_tabs" => tool_get_tabs(),
         "run_command" => tool_run_command(args),
         "get_output" => tool_get_output(args),
@@ -128,83 +244,81 @@ fn execute_tool(name: &str, args: serde_json::Value) -> ToolResult {
         "show_window" => tool_show_window(),
         "hide_window" => tool_hide_window(),
         "split_pane" => tool_split_pane(args),
+        "git_status" => tool_git_status(args),
+        "git_log" => tool_git_log(args),
+        "git_stage" => tool_git_stage(args),
+        "git_commit" => tool_git_commit(args),
+        "git_push" => tool_git_push(args),
+        "docker_ps" => tool_docker_ps(),
+        "docker_start" => tool_docker_start(args),
+        "docker_stop" => tool_docker_stop(args),
+        "docker_logs" => tool_docker_logs(args),
         _ => ToolResult::error(&format!("Unknown tool: {}", name)),
     }
 }
 
 fn send_to_app(action: &str, payload: serde_json::Value) -> Result<serde_json::Value, String> {
     let ipc_path = get_ipc_path();
+    let token = read_ipc_token()?;
+
+    let message = json!({
+        "action": action,
+        "payload": payload,
+        "token": token
+    });
 
     #[cfg(windows)]
-    {
-        use std::os::windows::io::AsRawSocket;
+    let stream = {
         use std::net::TcpStream;
-
-        match TcpStream::connect(&ipc_path) {
-            Ok(mut stream) => {
-                let message = json!({
-                    "action": action,
-                    "payload": payload
-                });
-
-                let msg_str = serde_json::to_string(&message).unwrap();
-                if let Err(e) = stream.write_all(msg_str.as_bytes()) {
-                    return Err(format!("Write failed: {}", e));
-                }
-                if let Err(e) = stream.write_all(b"\n") {
-                    return Err(format!("Write newline failed: {}", e));
-                }
-                if let Err(e) = stream.flush() {
-                    return Err(format!("Flush failed: {}", e));
-                }
-
-                let mut reader = io::BufReader::new(&stream);
-                let mut response = String::new();
-                if let Err(e) = reader.read_line(&mut response) {
-                    return Err(format!("Read failed: {}", e));
-                }
-
-                serde_json::from_str(&response)
-                    .map_err(|e| format!("Parse response failed: {}", e))
-            }
-            Err(e) => Err(format!("Connection failed: {}. Is WSL Terminal running?", e)),
-        }
-    }
+        TcpStream::connect(&ipc_path)
+    };
 
     #[cfg(not(windows))]
-    {
+    let stream = {
         use std::os::unix::net::UnixStream;
+        UnixStream::connect(&ipc_path)
+    };
+
+    match stream {
+        Ok(stream) => exchange_message(stream, &message),
+        Err(e) => Err(format!("Connection failed: {}. Is WSL Terminal running?", e)),
+    }
+}
 
-        match UnixStream::connect(&ipc_path) {
-            Ok(mut stream) => {
-                let message = json!({
-                    "action": action,
-                    "payload": payload
-                });
+/// Write one JSON message to the app and read back its one-line response
+fn exchange_message<S: io::Read + Write>(
+    mut stream: S,
+    message: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let msg_str = serde_json::to_string(message).unwrap();
+    if let Err(e) = stream.write_all(msg_str.as_bytes()) {
+        return Err(format!("Write failed: {}", e));
+    }
+    if let Err(e) = stream.write_all(b"\n") {
+        return Err(format!("Write newline failed: {}", e));
+    }
+    if let Err(e) = stream.flush() {
+        return Err(format!("Flush failed: {}", e));
+    }
 
-                let msg_str = serde_json::to_string(&message).unwrap();
-                if let Err(e) = stream.write_all(msg_str.as_bytes()) {
-                    return Err(format!("Write failed: {}", e));
-                }
-                if let Err(e) = stream.write_all(b"\n") {
-                    return Err(format!("Write newline failed: {}", e));
-                }
-                if let Err(e) = stream.flush() {
-                    return Err(format!("Flush failed: {}", e));
-                }
+    let mut reader = io::BufReader::new(stream);
+    let mut response = String::new();
+    if let Err(e) = reader.read_line(&mut response) {
+        return Err(format!("Read failed: {}", e));
+    }
 
-                let mut reader = io::BufReader::new(&stream);
-                let mut response = String::new();
-                if let Err(e) = reader.read_line(&mut response) {
-                    return Err(format!("Read failed: {}", e));
-                }
+    let value: serde_json::Value = serde_json::from_str(&response)
+        .map_err(|e| format!("Parse response failed: {}", e))?;
 
-                serde_json::from_str(&response)
-                    .map_err(|e| format!("Parse response failed: {}", e))
-            }
-            Err(e) => Err(format!("Connection failed: {}. Is WSL Terminal running?", e)),
-        }
+    // Errors raised by the app itself (auth, timeout, unknown action)
+    if let Some(error) = value.get("error").and_then(|v| v.as_str()) {
+        return match value.get("code").and_then(|v| v.as_str()) {
+            Some(code) => Err(format!("[{}] {}", code, error)),
+            None => Err(error.to_string()),
+        };
     }
+
+    Ok(value)
 }
 
 fn get_ipc_path() -> String {
@@ -261,6 +375,18 @@ fn tool_focus_tab(args: serde_json::Value) -> ToolResult {
     }
 }
 
+fn tool_rename_tab(args: serde_json::Value) -> ToolResult {
+    let params: RenameTabParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match send_to_app("rename_tab", serde_json::to_value(&params).unwrap()) {
+        Ok(_) => ToolResult::text(&format!("Renamed tab {} to: {}", params.tab_id, params.title)),
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
 fn tool_get_tabs() -> ToolResult {
     match send_to_app("get_tabs", json!({})) {
         Ok(response) => {
@@ -425,3 +551,166 @@ fn tool_split_pane(args: serde_json::Value) -> ToolResult {
         Err(e) => ToolResult::error(&e),
     }
 }
+
+/// Run one of the app's async command handlers from the synchronous MCP loop
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+// Git tools run in-process, so they work even when the GUI is not running
+
+fn tool_git_status(args: serde_json::Value) -> ToolResult {
+    let params: GitCwdParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::git_status(params.cwd)) {
+        Ok(status) => {
+            let formatted = serde_json::to_string_pretty(&status).unwrap_or_default();
+            ToolResult::text(&formatted)
+        }
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
+fn tool_git_log(args: serde_json::Value) -> ToolResult {
+    let params: GitLogParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::git_log(params.cwd, params.count)) {
+        Ok(commits) => {
+            let formatted = serde_json::to_string_pretty(&commits).unwrap_or_default();
+            ToolResult::text(&formatted)
+        }
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
+fn tool_git_stage(args: serde_json::Value) -> ToolResult {
+    let params: GitStageParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    let result = match &params.path {
+        Some(path) => block_on(crate::git_stage(params.cwd.clone(), path.clone())),
+        None => block_on(crate::git_stage_all(params.cwd.clone())),
+    };
+
+    match result {
+        Ok(()) => match params.path {
+            Some(path) => ToolResult::text(&format!("Staged: {}", path)),
+            None => ToolResult::text("Staged all changes"),
+        },
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
+fn tool_git_commit(args: serde_json::Value) -> ToolResult {
+    let params: GitCommitParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::git_commit(params.cwd, params.message)) {
+        Ok(hash) => ToolResult::text(&format!("Committed: {}", hash)),
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
+fn tool_git_push(args: serde_json::Value) -> ToolResult {
+    let params: GitCwdParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::git_push(params.cwd)) {
+        Ok(output) if output.is_empty() => ToolResult::text("Pushed"),
+        Ok(output) => ToolResult::text(&output),
+        Err(e) => ToolResult::error(&e),
+    }
+}
+
+// Docker tools also run in-process; a stopped daemon is reported as plain text, not an error
+
+/// Whether a docker error means the CLI or daemon is unreachable rather than a bad request
+fn is_docker_unavailable(error: &str) -> bool {
+    error.contains("Docker not available")
+        || error.contains("Cannot connect to the Docker daemon")
+        || error.contains("error during connect")
+}
+
+fn docker_error(error: &str) -> ToolResult {
+    if is_docker_unavailable(error) {
+        ToolResult::text(&format!("Docker is not available, is the Docker daemon running? ({})", error.trim()))
+    } else {
+        ToolResult::error(error)
+    }
+}
+
+fn tool_docker_ps() -> ToolResult {
+    match block_on(crate::docker_containers()) {
+        Ok(containers) => {
+            let formatted = serde_json::to_string_pretty(&containers).unwrap_or_default();
+            ToolResult::text(&formatted)
+        }
+        Err(e) => docker_error(&e),
+    }
+}
+
+fn tool_docker_start(args: serde_json::Value) -> ToolResult {
+    let params: DockerContainerParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::docker_start(params.container_id.clone())) {
+        Ok(()) => ToolResult::text(&format!("Started container: {}", params.container_id)),
+        Err(e) => docker_error(&e),
+    }
+}
+
+fn tool_docker_stop(args: serde_json::Value) -> ToolResult {
+    let params: DockerContainerParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::docker_stop(params.container_id.clone())) {
+        Ok(()) => ToolResult::text(&format!("Stopped container: {}", params.container_id)),
+        Err(e) => docker_error(&e),
+    }
+}
+
+fn tool_docker_logs(args: serde_json::Value) -> ToolResult {
+    let params: DockerLogsParams = match serde_json::from_value(args) {
+        Ok(p) => p,
+        Err(e) => return ToolResult::error(&format!("Invalid params: {}", e)),
+    };
+
+    match block_on(crate::docker_logs(params.container_id, Some(params.tail))) {
+        Ok(logs) => ToolResult::text(&logs),
+        Err(e) => docker_error(&e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_request_returns_batch_response() {
+        let line = r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","id":2,"method":"tools/list"}]"#;
+
+        let reply = handle_message(line).unwrap();
+        let responses = reply.as_array().unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(responses[1]["id"], json!(2));
+        assert!(responses[1]["result"]["tools"].is_array());
+    }
+}
@@ -1,8 +1,8 @@
 use super::types::*;
 use serde_json::json;
 
-pub fn get_tools() -> Vec<ToolInfo> {
-    vec![
+pub fn get_tools(docker_available: bool) -> Vec<ToolInfo> {
+    let mut tools = vec![
         ToolInfo {
             name: "open_tab".to_string(),
             description: "Open a new terminal tab with specified shell (wsl, powershell, cmd)".to_string(),
@@ -58,6 +58,24 @@ pub fn get_tools() -> Vec<ToolInfo> {
                 "required": ["tab_id"]
             }),
         },
+        ToolInfo {
+            name: "rename_tab".to_string(),
+            description: "Rename a terminal tab".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "tab_id": {
+                        "type": "string",
+                        "description": "The tab ID to rename"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "New tab title"
+                    }
+                },
+                "required": ["tab_id", "title"]
+            }),
+        },
         ToolInfo {
             name: "get_tabs".to_string(),
             description: "List all open tabs with their info (id, title, shell, active state)".to_string(),
@@ -87,8 +105,7 @@ pub fn get_tools() -> Vec<ToolInfo> {
                     },
                     "timeout_ms": {
                         "type": "integer",
-                        "description": "Timeout in milliseconds when waiting for output",
-                        "default": 5000
+                        "description": "Timeout in milliseconds, max 600000. Bounds how long the app may take to answer (30000 if omitted); with wait_for_output it is the output wait, and the app gets a 5000 ms margin on top"
                     }
                 },
                 "required": ["tab_id", "command"]
@@ -247,5 +264,155 @@ pub fn get_tools() -> Vec<ToolInfo> {
                 "required": ["tab_id", "direction"]
             }),
         },
+        ToolInfo {
+            name: "git_status".to_string(),
+            description: "Get git status of a repository (branch, upstream, ahead/behind, changed files)".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "cwd": {
+                        "type": "string",
+                        "description": "Repository path (Windows path or WSL path like /home/user/project)"
+                    }
+                },
+                "required": ["cwd"]
+            }),
+        },
+        ToolInfo {
+            name: "git_log".to_string(),
+            description: "Get recent commits of a repository".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "cwd": {
+                        "type": "string",
+                        "description": "Repository path (Windows path or WSL path like /home/user/project)"
+                    },
+                    "count": {
+                        "type": "integer",
+                        "description": "Number of commits to retrieve",
+                        "default": 20
+                    }
+                },
+                "required": ["cwd"]
+            }),
+        },
+        ToolInfo {
+            name: "git_stage".to_string(),
+            description: "Stage a file, or all changes if no path is given".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "cwd": {
+                        "type": "string",
+                        "description": "Repository path (Windows path or WSL path like /home/user/project)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File path relative to the repository (omit to stage all changes)"
+                    }
+                },
+                "required": ["cwd"]
+            }),
+        },
+        ToolInfo {
+            name: "git_commit".to_string(),
+            description: "Commit staged changes".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "cwd": {
+                        "type": "string",
+                        "description": "Repository path (Windows path or WSL path like /home/user/project)"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message"
+                    }
+                },
+                "required": ["cwd", "message"]
+            }),
+        },
+        ToolInfo {
+            name: "git_push".to_string(),
+            description: "Push the current branch to its remote".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "cwd": {
+                        "type": "string",
+                        "description": "Repository path (Windows path or WSL path like /home/user/project)"
+                    }
+                },
+                "required": ["cwd"]
+            }),
+        },
+    ];
+
+    if docker_available {
+        tools.extend(docker_tools());
+    }
+
+    tools
+}
+
+/// Docker tools, only advertised while the Docker daemon is reachable
+fn docker_tools() -> Vec<ToolInfo> {
+    vec![
+        ToolInfo {
+            name: "docker_ps".to_string(),
+            description: "List all docker containers (running and stopped)".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolInfo {
+            name: "docker_start".to_string(),
+            description: "Start a docker container".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "container_id": {
+                        "type": "string",
+                        "description": "Container ID or name"
+                    }
+                },
+                "required": ["container_id"]
+            }),
+        },
+        ToolInfo {
+            name: "docker_stop".to_string(),
+            description: "Stop a docker container".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "container_id": {
+                        "type": "string",
+                        "description": "Container ID or name"
+                    }
+                },
+                "required": ["container_id"]
+            }),
+        },
+        ToolInfo {
+            name: "docker_logs".to_string(),
+            description: "Get the last lines of a docker container's logs".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "container_id": {
+                        "type": "string",
+                        "description": "Container ID or name"
+                    },
+                    "tail": {
+                        "type": "integer",
+                        "description": "Number of lines to retrieve",
+                        "default": 100
+                    }
+                },
+                "required": ["container_id"]
+            }),
+        },
     ]
 }
@@ -11,6 +11,7 @@ import { useSuggestionStore } from "@/stores/suggestionStore";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 
 interface McpActionPayload {
+  request_id: number;
   action: string;
   payload: Record<string, unknown>;
 }
@@ -34,7 +35,7 @@ export function useMcpHandler() {
 
   const handleMcpAction = useCallback(
     async (event: McpActionPayload) => {
-      const { action, payload } = event;
+      const { request_id: requestId, action, payload } = event;
       let response: Record<string, unknown> = {};
 
       try {
@@ -73,6 +74,20 @@ export function useMcpHandler() {
             break;
           }
 
+          case "rename_tab": {
+            const tabId = payload.tab_id as string;
+            const title = payload.title as string;
+            if (tabId && title && tabs.find((t) => t.id === tabId)) {
+              updateTabTitle(tabId, title);
+              response = { success: true, title };
+            } else if (!title) {
+              response = { success: false, error: "Title not specified" };
+            } else {
+              response = { success: false, error: "Tab not found" };
+            }
+            break;
+          }
+
           case "get_tabs": {
             response = {
               tabs: tabs.map((tab) => ({
@@ -348,7 +363,7 @@ export function useMcpHandler() {
         response = { error: String(error) };
       }
 
-      await invoke("ipc_response", { response });
+      await invoke("ipc_response", { requestId, response });
     },
     [
       tabs,